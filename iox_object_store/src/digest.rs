@@ -0,0 +1,126 @@
+//! BLAKE3 content digests used to address parquet data by its bytes rather than by name.
+
+use snafu::{OptionExt, Snafu};
+use std::fmt;
+
+/// A BLAKE3 digest of some bytes, used as a stable, content-derived handle for an object.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct B3Digest([u8; 32]);
+
+impl B3Digest {
+    /// Compute the digest of `bytes`.
+    ///
+    /// Hashing an empty slice is well-defined and deterministic, so an empty-body write still
+    /// produces a stable digest.
+    pub fn compute(bytes: &[u8]) -> Self {
+        Self(*blake3::hash(bytes).as_bytes())
+    }
+
+    /// The lowercase hex encoding of this digest, used to build object store paths.
+    pub fn to_hex(&self) -> String {
+        let mut s = String::with_capacity(64);
+        for byte in &self.0 {
+            s.push_str(&format!("{:02x}", byte));
+        }
+        s
+    }
+
+    /// The first two hex characters of this digest, used as the shard prefix directory so that
+    /// listing and backend partitioning don't concentrate on a single hot prefix.
+    pub fn shard_prefix(&self) -> String {
+        self.to_hex()[..2].to_string()
+    }
+
+    /// Parse a digest from its lowercase hex encoding.
+    pub fn from_hex(hex: &str) -> Result<Self, DigestError> {
+        if hex.len() != 64 {
+            return WrongLength { length: hex.len() }.fail();
+        }
+        if !hex.is_ascii() {
+            return InvalidHex { hex }.fail();
+        }
+
+        let mut bytes = [0u8; 32];
+        for (i, byte) in bytes.iter_mut().enumerate() {
+            *byte = u8::from_str_radix(&hex[i * 2..i * 2 + 2], 16)
+                .ok()
+                .context(InvalidHex { hex })?;
+        }
+
+        Ok(Self(bytes))
+    }
+}
+
+impl fmt::Display for B3Digest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_hex())
+    }
+}
+
+/// Errors that can occur when decoding a [`B3Digest`] from its hex representation.
+#[derive(Debug, Snafu)]
+pub enum DigestError {
+    /// The hex string wasn't 64 characters long (32 bytes, two hex chars each).
+    #[snafu(display("Digest hex string had length {}, expected 64", length))]
+    WrongLength {
+        /// The actual length of the string that was given
+        length: usize,
+    },
+
+    /// The hex string contained non-hex characters.
+    #[snafu(display("Invalid hex digest: {}", hex))]
+    InvalidHex {
+        /// The invalid string
+        hex: String,
+    },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_input_has_a_deterministic_digest() {
+        let a = B3Digest::compute(b"");
+        let b = B3Digest::compute(b"");
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn hex_roundtrips() {
+        let digest = B3Digest::compute(b"hello world");
+        let hex = digest.to_hex();
+        assert_eq!(hex.len(), 64);
+        let parsed = B3Digest::from_hex(&hex).unwrap();
+        assert_eq!(digest, parsed);
+    }
+
+    #[test]
+    fn shard_prefix_is_first_two_hex_chars() {
+        let digest = B3Digest::compute(b"some parquet bytes");
+        assert_eq!(digest.shard_prefix(), &digest.to_hex()[..2]);
+        assert_eq!(digest.shard_prefix().len(), 2);
+    }
+
+    #[test]
+    fn from_hex_rejects_wrong_length() {
+        let err = B3Digest::from_hex("abcd").unwrap_err();
+        assert!(matches!(err, DigestError::WrongLength { length: 4 }));
+    }
+
+    #[test]
+    fn from_hex_rejects_non_hex_characters() {
+        let hex = "z".repeat(64);
+        let err = B3Digest::from_hex(&hex).unwrap_err();
+        assert!(matches!(err, DigestError::InvalidHex { .. }));
+    }
+
+    #[test]
+    fn from_hex_rejects_non_ascii_of_matching_byte_length_without_panicking() {
+        // "é" is 2 bytes, so 32 of them make a 64-byte string that isn't 64 hex characters.
+        let hex = "é".repeat(32);
+        assert_eq!(hex.len(), 64);
+        let err = B3Digest::from_hex(&hex).unwrap_err();
+        assert!(matches!(err, DigestError::InvalidHex { .. }));
+    }
+}
@@ -11,15 +11,62 @@
 
 //! Wraps the object_store crate with IOx-specific semantics.
 
-use bytes::Bytes;
+use bytes::{Bytes, BytesMut};
 use data_types::{server_id::ServerId, DatabaseName};
 use futures::{stream::BoxStream, Stream, StreamExt, TryStreamExt};
 use object_store::{
     path::{parsed::DirsAndFileName, ObjectStorePath, Path},
     ObjectStore, ObjectStoreApi, Result,
 };
+use snafu::{ResultExt, Snafu};
 use std::{io, sync::Arc};
 
+mod digest;
+pub use digest::{B3Digest, DigestError};
+
+mod uri;
+pub use uri::UriError;
+
+mod transaction;
+pub use transaction::{Transaction, TransactionError};
+
+mod retry;
+
+/// Errors returned by operations that can't be expressed with `object_store`'s own `Error` type,
+/// such as content-hash verification.
+#[derive(Debug, Snafu)]
+pub enum IoxObjectStoreError {
+    /// The underlying object store returned an error.
+    #[snafu(display("Error from the underlying object store: {}", source))]
+    UnderlyingObjectStoreError {
+        /// The underlying error
+        source: object_store::Error,
+    },
+
+    /// The bytes read back from object storage didn't hash to the digest that was requested.
+    #[snafu(display(
+        "Content hash mismatch for {}: expected {}, computed {}",
+        path,
+        expected,
+        computed
+    ))]
+    ContentHashMismatch {
+        /// The object store path that was read
+        path: String,
+        /// The digest that was requested
+        expected: B3Digest,
+        /// The digest actually computed from the bytes read back
+        computed: B3Digest,
+    },
+
+    /// [`IoxObjectStore::put_if_absent`] found an object already at the target location.
+    #[snafu(display("Object already exists at {}", path))]
+    AlreadyExists {
+        /// The object store path that already had an object at it
+        path: String,
+    },
+}
+
 /// Handles persistence of data for a particular database. Writes within its directory/prefix.
 #[derive(Debug)]
 pub struct IoxObjectStore {
@@ -37,7 +84,45 @@ impl IoxObjectStore {
         server_id: ServerId,
         database_name: &DatabaseName<'_>,
     ) -> Self {
-        let root_path = RootPath::new(store.new_path(), server_id, database_name);
+        Self::new_with_prefix(store, server_id, database_name, &[])
+    }
+
+    /// Parse a scheme-qualified location (`file://`, `memory://`, `s3://bucket/...`,
+    /// `gs://bucket/...`, `r2://bucket/...`) and instantiate the matching `ObjectStore` backend,
+    /// wiring credentials from `options` or the environment.
+    ///
+    /// This lets operators point a database at local disk for dev, in-memory for tests, and
+    /// S3/GCS/R2 (or anything else S3/GCS/R2-compatible, such as MinIO) in production without
+    /// changing call sites. Any leading path after the bucket/container name is folded into the
+    /// database's root path, so all `RelativePath`s still resolve under
+    /// `<server_id>/<db_name>`.
+    pub fn from_uri(
+        location_uri: &str,
+        options: &std::collections::HashMap<String, String>,
+        server_id: ServerId,
+        database_name: &DatabaseName<'_>,
+    ) -> std::result::Result<Self, uri::UriError> {
+        let parsed = uri::parse(location_uri)?;
+        let store = uri::build_object_store(&parsed, options)?;
+        Ok(Self::new_with_prefix(
+            Arc::new(store),
+            server_id,
+            database_name,
+            &parsed.prefix,
+        ))
+    }
+
+    fn new_with_prefix(
+        store: Arc<ObjectStore>,
+        server_id: ServerId,
+        database_name: &DatabaseName<'_>,
+        prefix: &[String],
+    ) -> Self {
+        let mut root = store.new_path();
+        for part in prefix {
+            root.push_dir(part);
+        }
+        let root_path = RootPath::new(root, server_id, database_name);
         Self {
             store,
             server_id,
@@ -51,22 +136,143 @@ impl IoxObjectStore {
         &self.database_name
     }
 
+    /// The ID of the server this object store is for.
+    pub fn server_id(&self) -> ServerId {
+        self.server_id
+    }
+
     /// Location where parquet data goes to.
     ///
     /// Schema currently is:
     ///
     /// ```text
-    /// <server_id>/<db_name>/data/
+    /// <root_path>/<server_id>/<db_name>/data/
     /// ```
+    ///
+    /// where `<root_path>` is empty unless [`Self::from_uri`] was given a leading bucket prefix.
     pub fn data_path(&self) -> Path {
-        let mut path = self.store.new_path();
-        path.push_dir(self.server_id.to_string());
-        path.push_dir(&self.database_name);
-        path.push_dir("data");
-        path
+        self.root_path.join(&RelativePath {
+            parts: vec!["data".to_string()],
+        })
+    }
+
+    /// Store a parquet chunk's bytes content-addressed under `data_path()`, sharded by the first
+    /// two hex characters of its BLAKE3 digest, and return that digest as the stable handle for
+    /// the object.
+    ///
+    /// Because the path is derived purely from the content, writing the same bytes twice is an
+    /// idempotent no-op: both writes resolve to the same object, giving natural dedup across
+    /// chunks and compactions. This checks what's already at the digest's path by re-hashing it
+    /// (the same check [`Self::get_by_digest`] does), rather than trusting its mere presence: a
+    /// writer that crashed mid-commit of a previous write can leave a corrupt object behind, and
+    /// treating that as "already there" would make every future write of the same content a
+    /// silent no-op, permanently stuck returning [`IoxObjectStoreError::ContentHashMismatch`] on
+    /// read with no path to repair it. So a hash mismatch here is instead treated as there being
+    /// nothing usable at the path yet, and the correct bytes are (re-)written over it.
+    pub async fn put_parquet_file(
+        &self,
+        bytes: Bytes,
+    ) -> std::result::Result<B3Digest, IoxObjectStoreError> {
+        let digest = B3Digest::compute(&bytes);
+        let final_path = self.content_addressed_path(&digest);
+
+        if self.digest_already_stored_at(&final_path, &digest).await {
+            return Ok(digest);
+        }
+
+        let staging_location =
+            staging_relative_path(&content_addressed_relative_path(&digest), rand::random());
+        let len = bytes.len();
+        self.stage_and_commit(
+            final_path,
+            staging_location,
+            futures::stream::once(async move { Ok(bytes) }),
+            Some(len),
+        )
+        .await
+        .context(UnderlyingObjectStoreError)?;
+
+        Ok(digest)
+    }
+
+    /// Whether the object at `final_path` is already the bytes that hash to `digest`: `false`
+    /// covers both "nothing there yet" and "something there, but it doesn't hash to `digest`"
+    /// (most likely a prior writer's commit that crashed partway through), so either way
+    /// [`Self::put_parquet_file`] knows it's safe to (re-)write.
+    async fn digest_already_stored_at(&self, final_path: &Path, digest: &B3Digest) -> bool {
+        let stream = match self.store.get(final_path).await {
+            Ok(stream) => stream,
+            Err(_) => return false,
+        };
+
+        let bytes = match stream
+            .try_fold(Vec::new(), |mut acc, chunk| async move {
+                acc.extend_from_slice(&chunk);
+                Ok(acc)
+            })
+            .await
+        {
+            Ok(bytes) => bytes,
+            Err(_) => return false,
+        };
+
+        &B3Digest::compute(&bytes) == digest
+    }
+
+    /// Fetch the parquet chunk previously stored with [`Self::put_parquet_file`], re-hashing the
+    /// bytes read back and erroring if they don't match `digest` so silent corruption in the
+    /// backing store doesn't go unnoticed.
+    pub async fn get_by_digest(
+        &self,
+        digest: &B3Digest,
+    ) -> std::result::Result<Bytes, IoxObjectStoreError> {
+        let path = self.content_addressed_path(digest);
+
+        let stream = self
+            .store
+            .get(&path)
+            .await
+            .context(UnderlyingObjectStoreError)?;
+        let bytes = stream
+            .try_fold(Vec::new(), |mut acc, chunk| async move {
+                acc.extend_from_slice(&chunk);
+                Ok(acc)
+            })
+            .await
+            .context(UnderlyingObjectStoreError)?;
+        let bytes = Bytes::from(bytes);
+
+        let computed = B3Digest::compute(&bytes);
+        if &computed != digest {
+            return ContentHashMismatch {
+                path: path.display(),
+                expected: *digest,
+                computed,
+            }
+            .fail();
+        }
+
+        Ok(bytes)
+    }
+
+    /// The object store path that `digest` resolves to.
+    fn content_addressed_path(&self, digest: &B3Digest) -> Path {
+        ContentAddressedPath::new(self.data_path()).for_digest(digest)
     }
 
     /// Store this data in this database's object store.
+    ///
+    /// The bytes are staged to a temporary object first, and only read back and written to
+    /// `location` once the full upload has succeeded, so a stream that errors or is dropped
+    /// partway through never leaves a partial object at `location` — the staging object is
+    /// deleted on any error so failures don't accumulate garbage. This protects against a
+    /// failing *input stream* only: the final write to `location` is a plain, unconditioned
+    /// `put` (the pinned `object_store` version exposes no atomic rename or conditional-put to
+    /// promote a staged object without a second upload), so a process crash during that specific
+    /// write can still leave a partial object at `location`. Callers that can detect a corrupt
+    /// read afterwards (as [`Self::replay`] does via [`TransactionError::TruncatedFrame`], and
+    /// [`Self::put_parquet_file`] does by re-hashing) have a way to notice; a caller of this
+    /// method directly does not, and should build in its own integrity check if it needs one.
     pub async fn put<S>(
         &self,
         location: &RelativePath,
@@ -76,53 +282,253 @@ impl IoxObjectStore {
     where
         S: Stream<Item = io::Result<Bytes>> + Send + Sync + 'static,
     {
-        let path = self.root_path.join(location);
-        self.store.put(&path, bytes, length).await
+        let final_path = self.root_path.join(location);
+        let staging_location = staging_relative_path(location, rand::random());
+        self.stage_and_commit(final_path, staging_location, bytes, length)
+            .await
     }
 
-    /// List the relative paths in this database's object store.
+    /// Like [`Self::put`], but refuses to overwrite an existing object at `location`, returning
+    /// [`IoxObjectStoreError::AlreadyExists`] instead. This narrows, but doesn't eliminate, the
+    /// race for concurrent writers of the same `location`: the existence check happens before
+    /// staging rather than as a single conditional write against the backend, so two callers
+    /// racing each other can both observe "not found" and both go on to write. [`Self::append_transaction`]
+    /// uses it to reject a writer appending at a revision that's already recorded; it doesn't
+    /// guarantee single-writer semantics outright.
+    pub async fn put_if_absent<S>(
+        &self,
+        location: &RelativePath,
+        bytes: S,
+        length: Option<usize>,
+    ) -> std::result::Result<(), IoxObjectStoreError>
+    where
+        S: Stream<Item = io::Result<Bytes>> + Send + Sync + 'static,
+    {
+        let final_path = self.root_path.join(location);
+
+        if self.store.get(&final_path).await.is_ok() {
+            return AlreadyExists {
+                path: final_path.display(),
+            }
+            .fail();
+        }
+
+        let staging_location = staging_relative_path(location, rand::random());
+        self.stage_and_commit(final_path, staging_location, bytes, length)
+            .await
+            .context(UnderlyingObjectStoreError)
+    }
+
+    /// Upload `bytes` to a temporary staging object, then, once the full upload has succeeded,
+    /// read it back and write it to `final_path`, deleting the staging object afterwards
+    /// regardless of outcome.
+    ///
+    /// This only uses `put`, `get`, and `delete`, which is all the pinned `object_store` version
+    /// is known to expose; there's no rename/copy primitive to promote the staging object into
+    /// place without a second upload, so the final write to `final_path` is not itself atomic —
+    /// see the caveat on [`Self::put`].
+    async fn stage_and_commit<S>(
+        &self,
+        final_path: Path,
+        staging_location: RelativePath,
+        bytes: S,
+        length: Option<usize>,
+    ) -> Result<()>
+    where
+        S: Stream<Item = io::Result<Bytes>> + Send + Sync + 'static,
+    {
+        let staging_path = self.root_path.join(&staging_location);
+
+        if let Err(source) = self.store.put(&staging_path, bytes, length).await {
+            let _ = self.store.delete(&staging_path).await;
+            return Err(source);
+        }
+
+        let commit_result = retry::with_retry(retry::RetryConfig::default(), || async {
+            let staged = self
+                .store
+                .get(&staging_path)
+                .await?
+                .try_fold(BytesMut::new(), |mut acc, chunk| async move {
+                    acc.extend_from_slice(&chunk);
+                    Ok(acc)
+                })
+                .await?
+                .freeze();
+
+            self.store
+                .put(
+                    &final_path,
+                    futures::stream::once(async move { Ok(staged) }),
+                    length,
+                )
+                .await
+        })
+        .await;
+
+        let _ = self.store.delete(&staging_path).await;
+
+        commit_result
+    }
+
+    /// List the relative paths in this database's object store, optionally restricted to those
+    /// under `prefix`, preserving the backend's native pagination.
+    ///
+    /// With no `prefix`, this still only lists under this database's own root, not the whole
+    /// backend: a bare `None` passed straight to the backend would list every database sharing
+    /// it, breaking the one-database-per-`IoxObjectStore` invariant the moment more than one
+    /// database uses the same backend.
     pub async fn list(
         &self,
-        _prefix: Option<&RelativePath>,
+        prefix: Option<&RelativePath>,
     ) -> Result<BoxStream<'static, Result<Vec<RelativePath>>>> {
-        unimplemented!()
-        // let path = prefix.map(|p| self.root_path.join(p));
-        // let store = Arc::clone(&self.store);
-        // let root_path = self.root_path.clone();
-        // Ok(store
-        //     .list(path.as_ref())
-        //     .await
-        //     .map(move |stream| {
-        //         stream.map_ok(move |list| {
-        //             list.into_iter()
-        //                 .map(|list_item| root_path.relative(list_item))
-        //                 .collect()
-        //         })
-        //     })?
-        //     .boxed())
-    }
-
-    /// List all the catalog transaction files in object storage for this database.
-    pub async fn catalog_transactions(
+        let path = Some(match prefix {
+            Some(prefix) => self.root_path.join(prefix),
+            None => self.root_path.root.clone(),
+        });
+        let root_path = self.root_path.clone();
+        Ok(self
+            .store
+            .list(path.as_ref())
+            .await?
+            .map_ok(move |list| {
+                list.into_iter()
+                    .map(|list_item| root_path.relative(list_item))
+                    .collect()
+            })
+            .boxed())
+    }
+
+    /// Append a catalog transaction to the log, as a new object under `transactions/` named
+    /// after its zero-padded revision so lexical object-store ordering equals numeric ordering.
+    ///
+    /// `txn.entries` are written as a length-delimited stream within that single object, in
+    /// the order given (callers should write them in dependency/root-to-leaves order so replay
+    /// can apply them sequentially). The write goes through [`Self::put_if_absent`] so appending
+    /// at a revision that's already recorded gets a [`TransactionError::RevisionAlreadyRecorded`]
+    /// instead of silently clobbering it. Two writers racing to append the same revision at the
+    /// same time can both pass that check, and the later write then replaces the earlier one.
+    pub async fn append_transaction(
         &self,
-    ) -> Result<BoxStream<'static, Result<Vec<Transaction>>>> {
-        Ok(self.list(Some(&RelativePath {
+        txn: &Transaction,
+    ) -> std::result::Result<(), TransactionError> {
+        let bytes = transaction::encode_frames(&txn.entries)?;
+        let len = bytes.len();
+
+        match self
+            .put_if_absent(
+                &transaction::revision_path(txn.revision),
+                futures::stream::once(async move { Ok(bytes) }),
+                Some(len),
+            )
+            .await
+        {
+            Ok(()) => Ok(()),
+            Err(IoxObjectStoreError::AlreadyExists { .. }) => {
+                transaction::RevisionAlreadyRecorded {
+                    revision: txn.revision,
+                }
+                .fail()
+            }
+            Err(IoxObjectStoreError::UnderlyingObjectStoreError { source }) => {
+                Err(source).context(transaction::UnderlyingObjectStoreError)
+            }
+            Err(IoxObjectStoreError::ContentHashMismatch { .. }) => {
+                unreachable!("put_if_absent never produces a content hash mismatch")
+            }
+        }
+    }
+
+    /// Replay the catalog transaction log in ascending revision order.
+    ///
+    /// A transaction object with a truncated trailing frame (the writer crashed mid-write) is
+    /// surfaced as an `Err` in the stream rather than causing a panic, so callers can decide
+    /// whether to treat it as the end of a recoverable log.
+    pub async fn replay(
+        &self,
+    ) -> std::result::Result<
+        BoxStream<'static, std::result::Result<Transaction, TransactionError>>,
+        TransactionError,
+    > {
+        let prefix = self.root_path.join(&RelativePath {
             parts: vec!["transactions".into()],
-        }))
-        .await?
-        .map_ok(|paths| paths.into_iter().map(Transaction::new).collect::<Vec<_>>())
-        .boxed())
-    }
-
-    // pub async fn list_with_delimiter(
-    //     &self,
-    //     prefix: &RelativePath,
-    // ) -> Result<ListResult<RelativePath>> {
-    //     let path = self.root_path.join(prefix);
-    //     self.store.list_with_delimiter(&path).await.map(|list| {
-    //
-    //     })
-    // }
+        });
+        let root_path = self.root_path.clone();
+
+        let raw_paths: Vec<Path> = self
+            .store
+            .list(Some(&prefix))
+            .await
+            .context(transaction::UnderlyingObjectStoreError)?
+            .try_fold(Vec::new(), |mut acc, chunk| async move {
+                acc.extend(chunk);
+                Ok(acc)
+            })
+            .await
+            .context(transaction::UnderlyingObjectStoreError)?;
+
+        // Only the bare `transactions/<revision>` objects are real transactions; a crashed
+        // `append_transaction` can leave `transactions/<revision>.tmp-<nonce>` staging debris
+        // behind (see `put_if_absent`), and its last path segment doesn't parse as a plain
+        // revision number, so it's filtered out here rather than replayed as a bogus revision 0.
+        let mut paths: Vec<(Path, u64)> = raw_paths
+            .into_iter()
+            .filter_map(|path| {
+                let revision = root_path.relative(path.clone()).parts.last()?.parse().ok()?;
+                Some((path, revision))
+            })
+            .collect();
+
+        // Revisions are zero-padded, so sorting the raw path strings also sorts by revision.
+        paths.sort_by_key(|(path, _)| path.display());
+
+        let store = Arc::clone(&self.store);
+
+        Ok(futures::stream::iter(paths)
+            .then(move |(path, revision)| {
+                let store = Arc::clone(&store);
+                async move {
+                    let display = path.display();
+
+                    let body = store
+                        .get(&path)
+                        .await
+                        .context(transaction::UnderlyingObjectStoreError)?;
+                    let bytes = body
+                        .try_fold(bytes::BytesMut::new(), |mut acc, chunk| async move {
+                            acc.extend_from_slice(&chunk);
+                            Ok(acc)
+                        })
+                        .await
+                        .context(transaction::UnderlyingObjectStoreError)?
+                        .freeze();
+
+                    let entries = transaction::decode_frames(&display, bytes)?;
+                    Ok(Transaction { revision, entries })
+                }
+            })
+            .boxed())
+    }
+
+    /// List the objects and common ("directory") prefixes directly under `prefix`, enabling
+    /// directory-style browsing of `data/` and `transactions/`.
+    pub async fn list_with_delimiter(&self, prefix: &RelativePath) -> Result<ListResult> {
+        let path = self.root_path.join(prefix);
+        let list = self.store.list_with_delimiter(&path).await?;
+
+        Ok(ListResult {
+            objects: list
+                .objects
+                .into_iter()
+                .map(|object| self.root_path.relative(object))
+                .collect(),
+            common_prefixes: list
+                .common_prefixes
+                .into_iter()
+                .map(|common_prefix| self.root_path.relative(common_prefix))
+                .collect(),
+        })
+    }
 
     /// Get the data in this relative path in this database's object store.
     pub async fn get(&self, location: &RelativePath) -> Result<BoxStream<'static, Result<Bytes>>> {
@@ -130,10 +536,21 @@ impl IoxObjectStore {
         self.store.get(&path).await
     }
 
-    // pub async fn delete(&self, location: &RelativePath) -> Result<()> {
-    //     let path = self.root_path.join(location);
-    //     self.store.delete(&path).await
-    // }
+    /// Delete the relative path from this database's object store.
+    pub async fn delete(&self, location: &RelativePath) -> Result<()> {
+        let path = self.root_path.join(location);
+        self.store.delete(&path).await
+    }
+}
+
+/// The result of [`IoxObjectStore::list_with_delimiter`]: the objects and common ("directory")
+/// prefixes found directly under the queried prefix, both relative to the database root.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ListResult {
+    /// Object-like entries directly under the prefix.
+    pub objects: Vec<RelativePath>,
+    /// Sub-"directory" prefixes directly under the prefix.
+    pub common_prefixes: Vec<RelativePath>,
 }
 
 /// A database-specific object store path that all `RelativePath`s should be within.
@@ -176,22 +593,63 @@ impl RootPath {
     }
 }
 
+/// A path under `data/` that locates objects by the BLAKE3 digest of their content rather than
+/// by name, sharded by the first two hex characters of the digest so listing and backend
+/// partitioning don't concentrate on a single hot prefix.
+#[derive(Debug, Clone)]
+struct ContentAddressedPath {
+    /// `data_path()` with a trailing `b3` directory.
+    root: Path,
+}
+
+impl ContentAddressedPath {
+    fn new(data_path: Path) -> Self {
+        let mut root = data_path;
+        root.push_dir("b3");
+        Self { root }
+    }
+
+    /// The path `digest` is always stored at, regardless of how `server_id` happens to format:
+    /// `<data_path>/b3/<2-char-prefix>/<full-hex-digest>.parquet`.
+    fn for_digest(&self, digest: &B3Digest) -> Path {
+        let mut path = self.root.clone();
+        path.push_dir(digest.shard_prefix());
+        path.set_file_name(format!("{}.parquet", digest.to_hex()));
+        path
+    }
+}
+
 /// A path within a database's object store directory. Must be combined with a database root path
 /// to get an object store path.
-#[derive(Debug)]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
 pub struct RelativePath {
     parts: Vec<String>,
 }
 
-#[derive(Debug)]
-pub struct Transaction {
-    relative_path: RelativePath,
+/// Where [`IoxObjectStore::content_addressed_path`] resolves to, expressed relative to the
+/// database root. Used only to name a staging location for
+/// [`IoxObjectStore::put_parquet_file`]'s upload.
+fn content_addressed_relative_path(digest: &B3Digest) -> RelativePath {
+    RelativePath {
+        parts: vec![
+            "data".to_string(),
+            "b3".to_string(),
+            digest.shard_prefix(),
+            format!("{}.parquet", digest.to_hex()),
+        ],
+    }
 }
 
-impl Transaction {
-    fn new(relative_path: RelativePath) -> Self {
-        Self { relative_path }
+/// The relative path `location` is staged at while its upload is in flight: the same path with
+/// `.tmp-<nonce>` appended to its final component, so a crashed writer's debris is easy to spot
+/// and doesn't collide with a concurrent staged write of the same `location`.
+fn staging_relative_path(location: &RelativePath, nonce: u64) -> RelativePath {
+    let mut parts = location.parts.clone();
+    match parts.last_mut() {
+        Some(last) => *last = format!("{}.tmp-{}", last, nonce),
+        None => parts.push(format!(".tmp-{}", nonce)),
     }
+    RelativePath { parts }
 }
 
 #[cfg(test)]
@@ -237,4 +695,458 @@ mod tests {
 
         assert_eq!(expected, root.join(&relative));
     }
+
+    #[tokio::test]
+    async fn put_parquet_file_round_trips_through_get_by_digest() {
+        let server_id = make_server_id();
+        let database_name = DatabaseName::new("clouds").unwrap();
+        let iox_object_store = IoxObjectStore::new(make_object_store(), server_id, &database_name);
+
+        let digest = iox_object_store
+            .put_parquet_file(Bytes::from_static(b"some parquet bytes"))
+            .await
+            .unwrap();
+
+        let round_tripped = iox_object_store.get_by_digest(&digest).await.unwrap();
+        assert_eq!(round_tripped, Bytes::from_static(b"some parquet bytes"));
+    }
+
+    #[tokio::test]
+    async fn put_parquet_file_is_idempotent_for_identical_bytes() {
+        let server_id = make_server_id();
+        let database_name = DatabaseName::new("clouds").unwrap();
+        let iox_object_store = IoxObjectStore::new(make_object_store(), server_id, &database_name);
+
+        let first = iox_object_store
+            .put_parquet_file(Bytes::from_static(b"identical"))
+            .await
+            .unwrap();
+        let second = iox_object_store
+            .put_parquet_file(Bytes::from_static(b"identical"))
+            .await
+            .unwrap();
+
+        assert_eq!(first, second);
+    }
+
+    #[tokio::test]
+    async fn empty_parquet_file_still_gets_a_deterministic_digest() {
+        let server_id = make_server_id();
+        let database_name = DatabaseName::new("clouds").unwrap();
+        let iox_object_store = IoxObjectStore::new(make_object_store(), server_id, &database_name);
+
+        let digest = iox_object_store.put_parquet_file(Bytes::new()).await.unwrap();
+        assert_eq!(digest, B3Digest::compute(b""));
+    }
+
+    #[test]
+    fn content_addressed_path_shards_by_first_two_hex_chars_of_digest() {
+        let object_store = make_object_store();
+        let server_id = make_server_id();
+        let database_name = DatabaseName::new("clouds").unwrap();
+        let iox_object_store = IoxObjectStore::new(object_store, server_id, &database_name);
+
+        let digest = B3Digest::compute(b"some parquet bytes");
+        let path = iox_object_store.content_addressed_path(&digest);
+
+        let hex = digest.to_hex();
+        let expected = format!("1/clouds/data/b3/{}/{}.parquet", &hex[..2], hex);
+        assert_eq!(path.display(), expected);
+    }
+
+    #[test]
+    fn from_uri_builds_an_in_memory_store() {
+        let server_id = make_server_id();
+        let database_name = DatabaseName::new("clouds").unwrap();
+        let options = std::collections::HashMap::new();
+
+        let iox_object_store =
+            IoxObjectStore::from_uri("memory://", &options, server_id, &database_name).unwrap();
+        assert_eq!(iox_object_store.data_path().display(), "1/clouds/data/");
+    }
+
+    #[test]
+    fn from_uri_folds_a_leading_prefix_into_the_database_root() {
+        let server_id = make_server_id();
+        let database_name = DatabaseName::new("clouds").unwrap();
+        let mut options = std::collections::HashMap::new();
+        options.insert("AWS_ACCESS_KEY_ID".to_string(), "test-key".to_string());
+        options.insert("AWS_SECRET_ACCESS_KEY".to_string(), "test-secret".to_string());
+        options.insert("AWS_DEFAULT_REGION".to_string(), "us-east-1".to_string());
+
+        let iox_object_store = IoxObjectStore::from_uri(
+            "s3://my-bucket/some/prefix",
+            &options,
+            server_id,
+            &database_name,
+        )
+        .unwrap();
+
+        assert_eq!(
+            iox_object_store.data_path().display(),
+            "some/prefix/1/clouds/data/"
+        );
+    }
+
+    #[test]
+    fn from_uri_missing_credentials_still_fails_cleanly() {
+        let server_id = make_server_id();
+        let database_name = DatabaseName::new("clouds").unwrap();
+        let options = std::collections::HashMap::new();
+
+        let err =
+            IoxObjectStore::from_uri("s3://my-bucket/some/prefix", &options, server_id, &database_name)
+                .unwrap_err();
+        assert!(matches!(err, UriError::MissingCredential { .. }));
+    }
+
+    #[test]
+    fn from_uri_rejects_unknown_scheme() {
+        let server_id = make_server_id();
+        let database_name = DatabaseName::new("clouds").unwrap();
+        let options = std::collections::HashMap::new();
+
+        let err =
+            IoxObjectStore::from_uri("ftp://somewhere", &options, server_id, &database_name)
+                .unwrap_err();
+        assert!(matches!(err, UriError::UnknownScheme { .. }));
+    }
+
+    #[tokio::test]
+    async fn replay_yields_transactions_in_ascending_revision_order() {
+        let server_id = make_server_id();
+        let database_name = DatabaseName::new("clouds").unwrap();
+        let iox_object_store = IoxObjectStore::new(make_object_store(), server_id, &database_name);
+
+        iox_object_store
+            .append_transaction(&Transaction {
+                revision: 2,
+                entries: vec![Bytes::from_static(b"second")],
+            })
+            .await
+            .unwrap();
+        iox_object_store
+            .append_transaction(&Transaction {
+                revision: 1,
+                entries: vec![
+                    Bytes::from_static(b"root entry"),
+                    Bytes::from_static(b"leaf entry"),
+                ],
+            })
+            .await
+            .unwrap();
+
+        let replayed: Vec<Transaction> = iox_object_store
+            .replay()
+            .await
+            .unwrap()
+            .try_collect()
+            .await
+            .unwrap();
+
+        assert_eq!(replayed.len(), 2);
+        assert_eq!(replayed[0].revision, 1);
+        assert_eq!(
+            replayed[0].entries,
+            vec![
+                Bytes::from_static(b"root entry"),
+                Bytes::from_static(b"leaf entry"),
+            ]
+        );
+        assert_eq!(replayed[1].revision, 2);
+    }
+
+    #[tokio::test]
+    async fn replay_surfaces_a_truncated_transaction_as_an_error_not_a_panic() {
+        let server_id = make_server_id();
+        let database_name = DatabaseName::new("clouds").unwrap();
+        let iox_object_store = IoxObjectStore::new(make_object_store(), server_id, &database_name);
+
+        let mut corrupt = bytes::BytesMut::new();
+        corrupt.extend_from_slice(&10u32.to_be_bytes());
+        corrupt.extend_from_slice(b"short");
+        iox_object_store
+            .put(
+                &transaction::revision_path(1),
+                futures::stream::once(async move { Ok(corrupt.freeze()) }),
+                None,
+            )
+            .await
+            .unwrap();
+
+        let results: Vec<_> = iox_object_store
+            .replay()
+            .await
+            .unwrap()
+            .collect()
+            .await;
+
+        assert_eq!(results.len(), 1);
+        assert!(matches!(
+            results[0],
+            Err(TransactionError::TruncatedFrame { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn replay_ignores_leftover_staging_debris_from_a_crashed_append() {
+        let server_id = make_server_id();
+        let database_name = DatabaseName::new("clouds").unwrap();
+        let iox_object_store = IoxObjectStore::new(make_object_store(), server_id, &database_name);
+
+        iox_object_store
+            .append_transaction(&Transaction {
+                revision: 1,
+                entries: vec![Bytes::from_static(b"first")],
+            })
+            .await
+            .unwrap();
+
+        // Simulate a crash partway through a later `append_transaction`: the staging object for
+        // revision 2 was written but never promoted to its final `transactions/0000000002` path.
+        let mut staged = transaction::revision_path(2);
+        staged.parts.last_mut().unwrap().push_str(".tmp-12345");
+        iox_object_store
+            .put(
+                &staged,
+                futures::stream::once(async move { Ok(transaction::encode_frames(&[]).unwrap()) }),
+                None,
+            )
+            .await
+            .unwrap();
+
+        let replayed: Vec<Transaction> = iox_object_store
+            .replay()
+            .await
+            .unwrap()
+            .try_collect()
+            .await
+            .unwrap();
+
+        assert_eq!(replayed.len(), 1);
+        assert_eq!(replayed[0].revision, 1);
+    }
+
+    fn relative_path(parts: &[&str]) -> RelativePath {
+        RelativePath {
+            parts: parts.iter().map(|part| part.to_string()).collect(),
+        }
+    }
+
+    async fn put_empty(iox_object_store: &IoxObjectStore, location: &RelativePath) {
+        iox_object_store
+            .put(location, futures::stream::once(async { Ok(Bytes::new()) }), None)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn list_with_no_prefix_lists_everything_under_the_database_root() {
+        let server_id = make_server_id();
+        let database_name = DatabaseName::new("clouds").unwrap();
+        let iox_object_store = IoxObjectStore::new(make_object_store(), server_id, &database_name);
+
+        put_empty(&iox_object_store, &relative_path(&["data", "one.parquet"])).await;
+        put_empty(
+            &iox_object_store,
+            &relative_path(&["transactions", "0000000001"]),
+        )
+        .await;
+
+        let listed: Vec<RelativePath> = iox_object_store
+            .list(None)
+            .await
+            .unwrap()
+            .try_concat()
+            .await
+            .unwrap();
+
+        assert_eq!(listed.len(), 2);
+        assert!(listed.contains(&relative_path(&["data", "one.parquet"])));
+        assert!(listed.contains(&relative_path(&["transactions", "0000000001"])));
+    }
+
+    #[tokio::test]
+    async fn list_with_no_prefix_does_not_leak_another_database_sharing_the_backend() {
+        let server_id = make_server_id();
+        let object_store = make_object_store();
+
+        let clouds = IoxObjectStore::new(
+            Arc::clone(&object_store),
+            server_id,
+            &DatabaseName::new("clouds").unwrap(),
+        );
+        let rain = IoxObjectStore::new(object_store, server_id, &DatabaseName::new("rain").unwrap());
+
+        put_empty(&clouds, &relative_path(&["data", "one.parquet"])).await;
+        put_empty(&rain, &relative_path(&["data", "other.parquet"])).await;
+
+        let listed: Vec<RelativePath> = clouds
+            .list(None)
+            .await
+            .unwrap()
+            .try_concat()
+            .await
+            .unwrap();
+
+        assert_eq!(listed, vec![relative_path(&["data", "one.parquet"])]);
+    }
+
+    #[tokio::test]
+    async fn list_with_a_nested_prefix_only_returns_matching_entries() {
+        let server_id = make_server_id();
+        let database_name = DatabaseName::new("clouds").unwrap();
+        let iox_object_store = IoxObjectStore::new(make_object_store(), server_id, &database_name);
+
+        put_empty(&iox_object_store, &relative_path(&["data", "one.parquet"])).await;
+        put_empty(
+            &iox_object_store,
+            &relative_path(&["transactions", "0000000001"]),
+        )
+        .await;
+
+        let listed: Vec<RelativePath> = iox_object_store
+            .list(Some(&relative_path(&["data"])))
+            .await
+            .unwrap()
+            .try_concat()
+            .await
+            .unwrap();
+
+        assert_eq!(listed, vec![relative_path(&["data", "one.parquet"])]);
+    }
+
+    #[tokio::test]
+    async fn list_with_delimiter_separates_common_prefixes_from_objects() {
+        let server_id = make_server_id();
+        let database_name = DatabaseName::new("clouds").unwrap();
+        let iox_object_store = IoxObjectStore::new(make_object_store(), server_id, &database_name);
+
+        put_empty(&iox_object_store, &relative_path(&["data", "one.parquet"])).await;
+        put_empty(&iox_object_store, &relative_path(&["data", "b3", "ab"])).await;
+
+        let listed = iox_object_store
+            .list_with_delimiter(&relative_path(&["data"]))
+            .await
+            .unwrap();
+
+        assert_eq!(listed.objects, vec![relative_path(&["data", "one.parquet"])]);
+        assert_eq!(listed.common_prefixes, vec![relative_path(&["data", "b3"])]);
+    }
+
+    #[tokio::test]
+    async fn relative_path_conversion_round_trips_through_list() {
+        let server_id = make_server_id();
+        let database_name = DatabaseName::new("clouds").unwrap();
+        let iox_object_store = IoxObjectStore::new(make_object_store(), server_id, &database_name);
+
+        let location = relative_path(&["data", "b3", "ab", "abc123.parquet"]);
+        put_empty(&iox_object_store, &location).await;
+
+        let listed: Vec<RelativePath> = iox_object_store
+            .list(None)
+            .await
+            .unwrap()
+            .try_concat()
+            .await
+            .unwrap();
+
+        assert_eq!(listed, vec![location]);
+    }
+
+    #[tokio::test]
+    async fn delete_removes_the_object() {
+        let server_id = make_server_id();
+        let database_name = DatabaseName::new("clouds").unwrap();
+        let iox_object_store = IoxObjectStore::new(make_object_store(), server_id, &database_name);
+
+        let location = relative_path(&["data", "one.parquet"]);
+        put_empty(&iox_object_store, &location).await;
+        iox_object_store.delete(&location).await.unwrap();
+
+        let listed: Vec<RelativePath> = iox_object_store
+            .list(None)
+            .await
+            .unwrap()
+            .try_concat()
+            .await
+            .unwrap();
+
+        assert!(listed.is_empty());
+    }
+
+    #[tokio::test]
+    async fn put_does_not_leave_a_staging_object_behind() {
+        let server_id = make_server_id();
+        let database_name = DatabaseName::new("clouds").unwrap();
+        let iox_object_store = IoxObjectStore::new(make_object_store(), server_id, &database_name);
+
+        let location = relative_path(&["data", "one.parquet"]);
+        put_empty(&iox_object_store, &location).await;
+
+        let listed: Vec<RelativePath> = iox_object_store
+            .list(None)
+            .await
+            .unwrap()
+            .try_concat()
+            .await
+            .unwrap();
+
+        assert_eq!(listed, vec![location]);
+    }
+
+    #[tokio::test]
+    async fn put_if_absent_refuses_to_overwrite_an_existing_object() {
+        let server_id = make_server_id();
+        let database_name = DatabaseName::new("clouds").unwrap();
+        let iox_object_store = IoxObjectStore::new(make_object_store(), server_id, &database_name);
+
+        let location = relative_path(&["transactions", "0000000001"]);
+        iox_object_store
+            .put_if_absent(
+                &location,
+                futures::stream::once(async { Ok(Bytes::from_static(b"first")) }),
+                None,
+            )
+            .await
+            .unwrap();
+
+        let err = iox_object_store
+            .put_if_absent(
+                &location,
+                futures::stream::once(async { Ok(Bytes::from_static(b"second")) }),
+                None,
+            )
+            .await
+            .unwrap_err();
+
+        assert!(matches!(err, IoxObjectStoreError::AlreadyExists { .. }));
+    }
+
+    #[tokio::test]
+    async fn put_if_absent_succeeds_when_nothing_is_there_yet() {
+        let server_id = make_server_id();
+        let database_name = DatabaseName::new("clouds").unwrap();
+        let iox_object_store = IoxObjectStore::new(make_object_store(), server_id, &database_name);
+
+        let location = relative_path(&["transactions", "0000000001"]);
+        iox_object_store
+            .put_if_absent(
+                &location,
+                futures::stream::once(async { Ok(Bytes::from_static(b"first")) }),
+                None,
+            )
+            .await
+            .unwrap();
+
+        let listed: Vec<RelativePath> = iox_object_store
+            .list(None)
+            .await
+            .unwrap()
+            .try_concat()
+            .await
+            .unwrap();
+
+        assert_eq!(listed, vec![location]);
+    }
 }
@@ -0,0 +1,120 @@
+//! A small retry wrapper for fallible object store operations, distinguishing transient backend
+//! errors (worth retrying) from permanent ones (not).
+
+use object_store::Error as ObjectStoreError;
+use std::{future::Future, time::Duration};
+
+/// How to retry a fallible object store operation.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct RetryConfig {
+    /// Maximum number of attempts, including the first.
+    pub(crate) max_attempts: usize,
+    /// Delay before the first retry; doubles after each subsequent attempt.
+    pub(crate) base_delay: Duration,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(100),
+        }
+    }
+}
+
+/// Whether an object store error is worth retrying.
+///
+/// This is necessarily a heuristic: without backend-specific status codes, a "not found" is
+/// treated as permanent (retrying won't change the answer), and everything else (timeouts,
+/// throttling, connection resets) is treated as potentially transient.
+fn is_transient(err: &ObjectStoreError) -> bool {
+    !matches!(err, ObjectStoreError::NotFound { .. })
+}
+
+/// Retry `operation` according to `config`, giving up and returning the last error once either a
+/// permanent error is seen or attempts are exhausted, rather than propagating a raw panic.
+pub(crate) async fn with_retry<F, Fut, T>(
+    config: RetryConfig,
+    mut operation: F,
+) -> Result<T, ObjectStoreError>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, ObjectStoreError>>,
+{
+    let mut attempt = 0;
+    let mut delay = config.base_delay;
+
+    loop {
+        attempt += 1;
+        match operation().await {
+            Ok(value) => return Ok(value),
+            Err(err) if attempt < config.max_attempts && is_transient(&err) => {
+                tokio::time::sleep(delay).await;
+                delay *= 2;
+            }
+            Err(err) => return Err(err),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[tokio::test]
+    async fn retries_transient_errors_until_success() {
+        let attempts = AtomicUsize::new(0);
+        let config = RetryConfig {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(1),
+        };
+
+        let result = with_retry(config, || async {
+            if attempts.fetch_add(1, Ordering::SeqCst) < 2 {
+                Err(ObjectStoreError::NotImplemented)
+            } else {
+                Ok(42)
+            }
+        })
+        .await;
+
+        assert_eq!(result.unwrap(), 42);
+        assert_eq!(attempts.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn gives_up_immediately_on_a_permanent_error() {
+        let attempts = AtomicUsize::new(0);
+        let config = RetryConfig::default();
+
+        let result: Result<(), ObjectStoreError> = with_retry(config, || async {
+            attempts.fetch_add(1, Ordering::SeqCst);
+            Err(ObjectStoreError::NotFound {
+                path: "missing".to_string(),
+            })
+        })
+        .await;
+
+        assert!(result.is_err());
+        assert_eq!(attempts.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn gives_up_after_max_attempts() {
+        let attempts = AtomicUsize::new(0);
+        let config = RetryConfig {
+            max_attempts: 2,
+            base_delay: Duration::from_millis(1),
+        };
+
+        let result: Result<(), ObjectStoreError> = with_retry(config, || async {
+            attempts.fetch_add(1, Ordering::SeqCst);
+            Err(ObjectStoreError::NotImplemented)
+        })
+        .await;
+
+        assert!(result.is_err());
+        assert_eq!(attempts.load(Ordering::SeqCst), 2);
+    }
+}
@@ -0,0 +1,184 @@
+//! The catalog transaction log: an append-only sequence of objects under `transactions/`, one
+//! per revision, each holding a length-delimited stream of serialized protobuf messages.
+
+use crate::RelativePath;
+use bytes::{Buf, BufMut, Bytes, BytesMut};
+use snafu::Snafu;
+
+/// The largest a single frame within a transaction object is allowed to be.
+pub(crate) const MAX_FRAME_LEN: usize = 1024 * 1024;
+
+/// Revisions are zero-padded to this width so lexical object-store ordering of the
+/// `transactions/` objects matches numeric revision ordering.
+const REVISION_WIDTH: usize = 10;
+
+/// A catalog transaction read from or to be appended to the log.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Transaction {
+    /// The monotonically increasing revision this transaction was (or will be) written at.
+    pub revision: u64,
+    /// Serialized protobuf messages making up this transaction, in the root-to-leaves
+    /// dependency order they must be replayed in.
+    pub entries: Vec<Bytes>,
+}
+
+/// Errors specific to reading or writing the transaction log.
+#[derive(Debug, Snafu)]
+#[snafu(visibility(pub(crate)))]
+pub enum TransactionError {
+    /// A transaction entry was larger than [`MAX_FRAME_LEN`].
+    #[snafu(display(
+        "Transaction entry of {} bytes exceeds the {} byte frame limit",
+        len,
+        max
+    ))]
+    EntryTooLarge {
+        /// The size of the offending entry
+        len: usize,
+        /// The maximum allowed entry size
+        max: usize,
+    },
+
+    /// A transaction object's trailing frame was cut short, meaning the writer crashed
+    /// mid-write.
+    #[snafu(display(
+        "Transaction log object {} ended with a truncated frame; the writer likely crashed \
+         mid-write",
+        path
+    ))]
+    TruncatedFrame {
+        /// The object that was truncated
+        path: String,
+    },
+
+    /// The underlying object store returned an error.
+    #[snafu(display("Error from the underlying object store: {}", source))]
+    UnderlyingObjectStoreError {
+        /// The underlying error
+        source: object_store::Error,
+    },
+
+    /// Another writer already appended a transaction at this revision.
+    #[snafu(display(
+        "A transaction was already recorded at revision {}; refusing to overwrite it",
+        revision
+    ))]
+    RevisionAlreadyRecorded {
+        /// The revision a transaction was already recorded at
+        revision: u64,
+    },
+}
+
+/// The relative path of the single object a revision's transaction is stored at.
+pub(crate) fn revision_path(revision: u64) -> RelativePath {
+    RelativePath {
+        parts: vec![
+            "transactions".to_string(),
+            format!("{:0width$}", revision, width = REVISION_WIDTH),
+        ],
+    }
+}
+
+/// Encode `entries` as a length-delimited stream: a big-endian `u32` length followed by that
+/// many bytes, per entry, concatenated in order.
+pub(crate) fn encode_frames(entries: &[Bytes]) -> Result<Bytes, TransactionError> {
+    let mut buf = BytesMut::new();
+
+    for entry in entries {
+        if entry.len() > MAX_FRAME_LEN {
+            return EntryTooLarge {
+                len: entry.len(),
+                max: MAX_FRAME_LEN,
+            }
+            .fail();
+        }
+
+        buf.put_u32(entry.len() as u32);
+        buf.extend_from_slice(entry);
+    }
+
+    Ok(buf.freeze())
+}
+
+/// Decode a length-delimited stream produced by [`encode_frames`] back into its entries.
+///
+/// A partial length prefix or a length prefix whose payload got cut short is treated as a
+/// crashed write and reported as [`TransactionError::TruncatedFrame`] rather than panicking.
+pub(crate) fn decode_frames(
+    path: &str,
+    mut bytes: Bytes,
+) -> Result<Vec<Bytes>, TransactionError> {
+    let mut entries = Vec::new();
+
+    while !bytes.is_empty() {
+        if bytes.len() < 4 {
+            return TruncatedFrame { path }.fail();
+        }
+        let len = bytes.get_u32() as usize;
+
+        if bytes.len() < len {
+            return TruncatedFrame { path }.fail();
+        }
+        entries.push(bytes.split_to(len));
+    }
+
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn revision_path_is_zero_padded_for_lexical_ordering() {
+        let early = revision_path(7);
+        let late = revision_path(12);
+        assert_eq!(early.parts, vec!["transactions", "0000000007"]);
+        assert_eq!(late.parts, vec!["transactions", "0000000012"]);
+        assert!(early.parts < late.parts);
+    }
+
+    #[test]
+    fn frames_round_trip() {
+        let entries = vec![
+            Bytes::from_static(b"root entry"),
+            Bytes::from_static(b"leaf entry"),
+        ];
+        let encoded = encode_frames(&entries).unwrap();
+        let decoded = decode_frames("test", encoded).unwrap();
+        assert_eq!(decoded, entries);
+    }
+
+    #[test]
+    fn empty_entries_round_trip() {
+        let encoded = encode_frames(&[]).unwrap();
+        let decoded = decode_frames("test", encoded).unwrap();
+        assert!(decoded.is_empty());
+    }
+
+    #[test]
+    fn decode_reports_truncated_trailing_payload() {
+        let mut encoded = BytesMut::new();
+        encoded.put_u32(10);
+        encoded.extend_from_slice(b"short");
+        let err = decode_frames("test", encoded.freeze()).unwrap_err();
+        assert!(matches!(err, TransactionError::TruncatedFrame { .. }));
+    }
+
+    #[test]
+    fn decode_reports_truncated_trailing_length_prefix() {
+        let mut encoded = BytesMut::new();
+        encoded.put_u32(4);
+        encoded.extend_from_slice(b"ok!!");
+        encoded.extend_from_slice(&[0u8, 0u8]); // a length prefix cut short
+        let err = decode_frames("test", encoded.freeze()).unwrap_err();
+        assert!(matches!(err, TransactionError::TruncatedFrame { .. }));
+    }
+
+    #[test]
+    fn encode_rejects_entries_over_the_frame_limit() {
+        let entries = vec![Bytes::from(vec![0u8; MAX_FRAME_LEN + 1])];
+        let err = encode_frames(&entries).unwrap_err();
+        assert!(matches!(err, TransactionError::EntryTooLarge { .. }));
+    }
+}
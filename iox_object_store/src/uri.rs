@@ -0,0 +1,187 @@
+//! Parsing of scheme-qualified object store locations (`file://`, `memory://`, `s3://`,
+//! `gs://`, `r2://`) into a concrete `ObjectStore` backend.
+
+use object_store::ObjectStore;
+use snafu::{OptionExt, ResultExt, Snafu};
+use std::collections::HashMap;
+
+/// Errors that can occur while parsing a URI into an `ObjectStore` or while constructing the
+/// backend it names.
+#[derive(Debug, Snafu)]
+pub enum UriError {
+    /// The URI didn't have a `scheme://...` shape at all, or named a bucket-based scheme with
+    /// nothing after the `://`.
+    #[snafu(display(
+        "Malformed object store URI `{}`: expected `scheme://...` with a bucket for cloud schemes",
+        uri
+    ))]
+    MalformedUri {
+        /// The URI that couldn't be parsed
+        uri: String,
+    },
+
+    /// The URI named a scheme this crate doesn't know how to construct a backend for.
+    #[snafu(display("Unknown object store URI scheme `{}`", scheme))]
+    UnknownScheme {
+        /// The unrecognized scheme
+        scheme: String,
+    },
+
+    /// A cloud backend was requested but a required credential wasn't present in `options` or
+    /// the environment.
+    #[snafu(display(
+        "Missing credential `{}` for `{}://` object store (set it in options or the environment)",
+        key,
+        scheme
+    ))]
+    MissingCredential {
+        /// The scheme that needed the credential
+        scheme: String,
+        /// The missing option/environment variable name
+        key: String,
+    },
+
+    /// The underlying object store backend couldn't be constructed.
+    #[snafu(display("Could not create `{}://` object store: {}", scheme, source))]
+    BackendError {
+        /// The scheme of the backend that failed to construct
+        scheme: String,
+        /// The underlying error
+        source: object_store::Error,
+    },
+}
+
+/// A parsed, scheme-qualified object store location: the backend to construct plus any leading
+/// path prefix that should be folded into the database's root path.
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct ParsedUri {
+    pub(crate) scheme: String,
+    pub(crate) bucket: String,
+    pub(crate) prefix: Vec<String>,
+}
+
+/// Split `scheme://bucket/some/prefix` into its scheme, bucket/container name, and any leading
+/// path prefix (which is folded into the database's `RootPath` so all `RelativePath`s still
+/// resolve under `<server_id>/<db_name>`).
+pub(crate) fn parse(uri: &str) -> Result<ParsedUri, UriError> {
+    let (scheme, rest) = uri.split_once("://").context(MalformedUri { uri })?;
+
+    // `file://` has no bucket concept: the whole remainder is the root directory on disk.
+    // `memory://` doesn't address anything external at all.
+    if scheme == "file" || scheme == "memory" {
+        return Ok(ParsedUri {
+            scheme: scheme.to_string(),
+            bucket: rest.to_string(),
+            prefix: Vec::new(),
+        });
+    }
+
+    if rest.is_empty() {
+        return MalformedUri { uri }.fail();
+    }
+
+    let mut parts = rest.split('/').filter(|part| !part.is_empty());
+    let bucket = parts.next().unwrap_or_default().to_string();
+    let prefix = parts.map(String::from).collect();
+
+    Ok(ParsedUri {
+        scheme: scheme.to_string(),
+        bucket,
+        prefix,
+    })
+}
+
+/// Look up a credential by `key` first in `options`, then in the environment variable of the
+/// same name.
+fn credential(
+    scheme: &str,
+    options: &HashMap<String, String>,
+    key: &str,
+) -> Result<String, UriError> {
+    options
+        .get(key)
+        .cloned()
+        .or_else(|| std::env::var(key).ok())
+        .context(MissingCredential { scheme, key })
+}
+
+/// Construct the `ObjectStore` backend named by `parsed`, wiring credentials from `options` or
+/// the environment.
+pub(crate) fn build_object_store(
+    parsed: &ParsedUri,
+    options: &HashMap<String, String>,
+) -> Result<ObjectStore, UriError> {
+    match parsed.scheme.as_str() {
+        "file" => Ok(ObjectStore::new_file(&parsed.bucket)),
+        "memory" => Ok(ObjectStore::new_in_memory()),
+        "s3" => {
+            let access_key_id = credential("s3", options, "AWS_ACCESS_KEY_ID")?;
+            let secret_access_key = credential("s3", options, "AWS_SECRET_ACCESS_KEY")?;
+            let region = credential("s3", options, "AWS_DEFAULT_REGION")?;
+            ObjectStore::new_amazon_s3(access_key_id, secret_access_key, region, &parsed.bucket)
+                .context(BackendError { scheme: "s3" })
+        }
+        "gs" => {
+            let service_account = credential("gs", options, "GOOGLE_SERVICE_ACCOUNT")?;
+            ObjectStore::new_google_cloud_storage(service_account, &parsed.bucket)
+                .context(BackendError { scheme: "gs" })
+        }
+        "r2" => {
+            let account_id = credential("r2", options, "R2_ACCOUNT_ID")?;
+            let access_key_id = credential("r2", options, "R2_ACCESS_KEY_ID")?;
+            let secret_access_key = credential("r2", options, "R2_SECRET_ACCESS_KEY")?;
+            ObjectStore::new_cloudflare_r2(
+                account_id,
+                access_key_id,
+                secret_access_key,
+                &parsed.bucket,
+            )
+            .context(BackendError { scheme: "r2" })
+        }
+        other => UnknownScheme {
+            scheme: other.to_string(),
+        }
+        .fail(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_scheme_bucket_and_no_prefix() {
+        let parsed = parse("memory://").unwrap();
+        assert_eq!(parsed.scheme, "memory");
+        assert_eq!(parsed.bucket, "");
+        assert!(parsed.prefix.is_empty());
+    }
+
+    #[test]
+    fn parses_bucket_and_leading_prefix() {
+        let parsed = parse("s3://my-bucket/some/prefix").unwrap();
+        assert_eq!(parsed.scheme, "s3");
+        assert_eq!(parsed.bucket, "my-bucket");
+        assert_eq!(parsed.prefix, vec!["some".to_string(), "prefix".to_string()]);
+    }
+
+    #[test]
+    fn rejects_uri_without_scheme_separator() {
+        let err = parse("not-a-uri").unwrap_err();
+        assert!(matches!(err, UriError::MalformedUri { .. }));
+    }
+
+    #[test]
+    fn rejects_unknown_scheme() {
+        let parsed = parse("ftp://somewhere").unwrap();
+        let err = build_object_store(&parsed, &HashMap::new()).unwrap_err();
+        assert!(matches!(err, UriError::UnknownScheme { .. }));
+    }
+
+    #[test]
+    fn rejects_missing_credential() {
+        let parsed = parse("s3://my-bucket").unwrap();
+        let err = build_object_store(&parsed, &HashMap::new()).unwrap_err();
+        assert!(matches!(err, UriError::MissingCredential { .. }));
+    }
+}